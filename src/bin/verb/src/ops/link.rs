@@ -1,6 +1,8 @@
 use crate::unit::{Language, Net, Unit};
 use cliproc::{cli, proc, stage::Memory};
 use cliproc::{Arg, Cli, Help, Subcommand};
+use rustyline::DefaultEditor;
+use std::collections::HashMap;
 
 pub struct Link {
     json: Unit,
@@ -11,6 +13,18 @@ pub struct Link {
     // use an 'exclude' list to ignore ports in the bfm
     exclude: Vec<String>,
     list: bool,
+    // per-port overrides for the send/recv data format, keyed as "port=fmt"
+    radix: Vec<String>,
+    // per-port overrides for the recv comparison mode, keyed as "port=mode"
+    compare: Vec<String>,
+    repl: bool,
+    // "file" or "socket"
+    transport: String,
+    // splice generated blocks into this file's contents and print the result
+    // (the file itself is only written with `in_place`)
+    emit: Option<String>,
+    // write the spliced result back to `emit` instead of printing it
+    in_place: bool,
 }
 
 impl Subcommand<()> for Link {
@@ -21,15 +35,31 @@ impl Subcommand<()> for Link {
             send: cli.check(Arg::flag("send"))?,
             comp: cli.check(Arg::flag("recv"))?,
             list: cli.check(Arg::flag("list"))?,
+            repl: cli.check(Arg::flag("repl"))?,
             bfm_inst: cli.get_all(Arg::option("if-inst").value("name"))?,
             exclude: cli
                 .get_all(Arg::option("exclude").switch('x').value("port"))?
                 .unwrap_or(Vec::new()),
+            radix: cli
+                .get_all(Arg::option("radix").value("port=fmt"))?
+                .unwrap_or(Vec::new()),
+            compare: cli
+                .get_all(Arg::option("compare").value("port=mode"))?
+                .unwrap_or(Vec::new()),
+            transport: cli
+                .get(Arg::option("transport").value("kind"))?
+                .unwrap_or(String::from("file")),
+            emit: cli.get(Arg::option("emit").value("file"))?,
+            in_place: cli.check(Arg::flag("in-place"))?,
             json: cli.require(Arg::positional("json"))?,
         })
     }
 
     fn execute(self, _c: &()) -> proc::Result {
+        if self.repl == true {
+            return self.run_repl();
+        }
+
         let filtered_ports: Vec<&Net> = self
             .json
             .get_ports()
@@ -54,6 +84,7 @@ impl Subcommand<()> for Link {
         }
 
         let mut space_next_display = false;
+        let mut regions: Vec<(String, String)> = Vec::new();
 
         if self.bfm == true {
             let result = match &self.json.get_language() {
@@ -65,17 +96,21 @@ impl Subcommand<()> for Link {
                     self.json.get_generics(),
                     &self.json.get_identifier(),
                 ),
-                Language::Verilog => todo!(),
+                Language::Verilog => {
+                    Self::verilog_to_string_bfm(&filtered_ports, &self.json.get_identifier())
+                }
             };
-            println!("{}", result);
-            space_next_display = true;
+            Self::display(
+                self.emit.is_some(),
+                "if",
+                result,
+                &mut regions,
+                &mut space_next_display,
+            );
         }
 
         if let Some(bfms) = &self.bfm_inst {
             for bfm_inst in bfms {
-                if space_next_display == true {
-                    println!();
-                }
                 let result = match &self.json.get_language() {
                     Language::Vhdl => {
                         Self::vhdl_to_string_bfm_inst(&self.json.get_identifier(), bfm_inst)
@@ -85,17 +120,25 @@ impl Subcommand<()> for Link {
                         &self.json.get_generics(),
                         bfm_inst,
                     ),
-                    Language::Verilog => todo!(),
+                    Language::Verilog => {
+                        Self::verilog_to_string_bfm_inst(&filtered_ports, bfm_inst)
+                    }
                 };
-                println!("{}", result);
-                space_next_display = true;
+                Self::display(
+                    self.emit.is_some(),
+                    &format!("if-inst:{}", bfm_inst),
+                    result,
+                    &mut regions,
+                    &mut space_next_display,
+                );
             }
         }
 
+        let formats = Self::build_format_map(&self.radix);
+        let compares = Self::build_compare_map(&self.compare);
+        let transport = Self::parse_transport(&self.transport);
+
         if self.send == true {
-            if space_next_display == true {
-                println!();
-            }
             let filtered_ports: Vec<&Net> = filtered_ports
                 .clone()
                 .into_iter()
@@ -103,37 +146,70 @@ impl Subcommand<()> for Link {
                 .collect();
 
             let result = match &self.json.get_language() {
-                Language::Vhdl => Self::vhdl_to_string_send(&filtered_ports, "bfm"),
-                Language::SystemVerilog => Self::sv_to_string_send(&filtered_ports, "bfm"),
-                Language::Verilog => todo!(),
+                Language::Vhdl => {
+                    Self::vhdl_to_string_send(&filtered_ports, "bfm", &formats, &transport)
+                }
+                Language::SystemVerilog => {
+                    Self::sv_to_string_send(&filtered_ports, "bfm", &formats, &transport)
+                }
+                Language::Verilog => {
+                    Self::verilog_to_string_send(&filtered_ports, "bfm", &formats, &transport)
+                }
             };
-            println!("{}", result);
-            space_next_display = true;
+            Self::display(
+                self.emit.is_some(),
+                "send",
+                result,
+                &mut regions,
+                &mut space_next_display,
+            );
         }
 
         if self.comp == true {
-            if space_next_display == true {
-                println!();
-            }
             let filtered_ports: Vec<&Net> = filtered_ports
                 .into_iter()
                 .filter(|n| n.is_output())
                 .collect();
 
             let result = match &self.json.get_language() {
-                Language::Vhdl => {
-                    Self::vhdl_to_string_comp(&filtered_ports, &self.json.get_identifier(), "bfm")
-                }
+                Language::Vhdl => Self::vhdl_to_string_comp(
+                    &filtered_ports,
+                    &self.json.get_identifier(),
+                    "bfm",
+                    &formats,
+                    &compares,
+                    &transport,
+                ),
                 Language::SystemVerilog => Self::sv_to_string_comp(
                     &filtered_ports,
                     &self.json.get_identifier(),
                     "bfm",
                     "mdl",
+                    &formats,
+                    &compares,
+                    &transport,
+                ),
+                Language::Verilog => Self::verilog_to_string_comp(
+                    &filtered_ports,
+                    &self.json.get_identifier(),
+                    "bfm",
+                    "mdl",
+                    &formats,
+                    &compares,
+                    &transport,
                 ),
-                Language::Verilog => todo!(),
             };
-            println!("{}", result);
-            // space_next_display = true;
+            Self::display(
+                self.emit.is_some(),
+                "recv",
+                result,
+                &mut regions,
+                &mut space_next_display,
+            );
+        }
+
+        if let Some(path) = &self.emit {
+            return Self::splice_into_file(path, self.json.get_language(), &regions, self.in_place);
         }
         Ok(())
     }
@@ -149,13 +225,42 @@ Args:
     <json>          hw unit's interface encoded in json format
 
 Options:
-    --if                  display the hw dut interface
+    --if                  display the hw dut interface (no-op for verilog;
+                          see --if-inst)
     --if-inst <name>...   display an instance of the hw dut interface
     --send                display the hw function to send inputs to the dut
     --recv                display the hw function to check outputs from the dut
-    --exclude, -x <port>... 
+    --exclude, -x <port>...
                           omit specific ports from the code snippets
+    --radix <port=fmt>... override a port's send/recv data format
+                          (fmt: bin, hex, dec, signed; append ':<n>' for a
+                          value spanning n whitespace-delimited words)
+    --compare <port=mode>...
+                          override a port's recv comparison mode
+                          (mode: eq, mask:<bits>, approx:<tol>, ignore)
+    --transport <kind>    select the send/recv data source: file (default)
+                          or socket, for live co-simulation
+    --emit <file>         splice the requested snippets into the contents of
+                          <file> between @generated markers and print the
+                          result (see --in-place)
+    --in-place            write the spliced result back to <file> instead
+                          of printing it (requires --emit)
     --list                list the port order and exit
+    --repl                enter an interactive session for exploring snippets
+";
+
+const REPL_HELP: &str = "\
+commands:
+    if                show the dut interface snippet
+    inst <name>       show an instance of the dut interface named <name>
+    send              show the function that sends inputs to the dut
+    recv              show the function that checks outputs from the dut
+    list              list the port order
+    exclude <port>    toggle a port in/out of the generated snippets
+    lang <l>          switch the target language (vhdl, sv, verilog)
+    transport <t>     switch the send/recv data source (file, socket)
+    help              print this message
+    quit              leave the session
 ";
 
 const VHDL_HEAD_COMMENT: &str = "-- This procedure is automatically @generated by Verb.\n-- It is not intended for manual editing.\n";
@@ -164,7 +269,459 @@ const VHDL_HEAD_COMMENT_RECORD: &str = "-- This record is automatically @generat
 const SV_HEAD_COMMENT: &str = "// This task is automatically @generated by Verb.\n// It is not intended for manual editing.\n";
 const SV_HEAD_COMMENT_INTERFACE: &str = "// This interface is automatically @generated by Verb.\n// It is not intended for manual editing.\n";
 
+const V_HEAD_COMMENT: &str = "// This task is automatically @generated by Verb.\n// It is not intended for manual editing.\n";
+
+#[derive(Clone, Copy)]
+struct Format {
+    radix: Radix,
+    // number of whitespace-delimited words a single value spans (e.g. a
+    // wide bus split across columns)
+    words: usize,
+}
+
+impl Format {
+    const DEFAULT: Self = Self {
+        radix: Radix::Bin,
+        words: 1,
+    };
+
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.splitn(2, ':');
+        let radix = Radix::parse(parts.next().unwrap_or(""))?;
+        let words = match parts.next() {
+            Some(n) => n
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a valid word count", n))?,
+            None => 1,
+        };
+        Ok(Self { radix, words })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Radix {
+    Bin,
+    Hex,
+    Dec,
+    Signed,
+}
+
+impl Radix {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "bin" => Ok(Self::Bin),
+            "hex" => Ok(Self::Hex),
+            "dec" => Ok(Self::Dec),
+            "signed" => Ok(Self::Signed),
+            _ => Err(format!("'{}' is not a supported radix format", s)),
+        }
+    }
+
+    fn format_spec(&self) -> &'static str {
+        match self {
+            Self::Bin => "%b",
+            Self::Hex => "%h",
+            Self::Dec => "%d",
+            Self::Signed => "%d",
+        }
+    }
+
+    fn vhdl_suffix(&self) -> &'static str {
+        match self {
+            Self::Bin => "",
+            Self::Hex => "_hex",
+            Self::Dec => "_dec",
+            Self::Signed => "_signed",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Compare {
+    Eq,
+    Mask(u32),
+    Approx(f64),
+    Ignore,
+}
+
+impl Compare {
+    const DEFAULT: Self = Self::Eq;
+
+    fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "eq" => Ok(Self::Eq),
+            "ignore" => Ok(Self::Ignore),
+            _ => {
+                if let Some(bits) = spec.strip_prefix("mask:") {
+                    return bits
+                        .parse::<u32>()
+                        .map(Self::Mask)
+                        .map_err(|_| format!("'{}' is not a valid mask", bits));
+                }
+                if let Some(tol) = spec.strip_prefix("approx:") {
+                    return tol
+                        .parse::<f64>()
+                        .map(Self::Approx)
+                        .map_err(|_| format!("'{}' is not a valid tolerance", tol));
+                }
+                Err(format!("'{}' is not a supported compare mode", spec))
+            }
+        }
+    }
+
+    // None means `name` should still be loaded but not checked
+    fn assert_call(&self, lhs: &str, rhs: &str, name: &str) -> Option<String> {
+        match self {
+            Self::Eq => Some(format!("assert_eq({}, {}, \"{}\");\n", lhs, rhs, name)),
+            Self::Mask(bits) => Some(format!(
+                "assert_eq_masked({}, {}, {}, \"{}\");\n",
+                lhs, rhs, bits, name
+            )),
+            Self::Approx(tol) => Some(format!(
+                "assert_approx({}, {}, {}, \"{}\");\n",
+                lhs, rhs, tol, name
+            )),
+            Self::Ignore => None,
+        }
+    }
+}
+
+// File is the original generate-then-replay flow; Socket drives a
+// co-simulation against a companion model instead
+#[derive(Clone, Copy)]
+enum Transport {
+    File,
+    Socket,
+}
+
+impl Transport {
+    const DEFAULT: Self = Self::File;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "file" => Ok(Self::File),
+            "socket" => Ok(Self::Socket),
+            _ => Err(format!("'{}' is not a supported transport", s)),
+        }
+    }
+}
+
+// shared variable holding the socket/named-pipe handle opened at elaboration
+const SOCKET_ENDPOINT: &str = "bfm_socket";
+// number of times the testbench retries confirming a sampled output before
+// giving up
+const SOCKET_CONFIRM_RETRIES: usize = 3;
+
 impl Link {
+    fn build_format_map(specs: &Vec<String>) -> HashMap<String, Format> {
+        let mut formats = HashMap::new();
+        for spec in specs {
+            if let Some((port, fmt)) = spec.split_once('=') {
+                match Format::parse(fmt) {
+                    Ok(format) => {
+                        formats.insert(port.to_string(), format);
+                    }
+                    Err(e) => eprintln!("warning: ignoring '--radix {}': {}", spec, e),
+                }
+            }
+        }
+        formats
+    }
+
+    fn format_of(formats: &HashMap<String, Format>, port: &str) -> Format {
+        formats.get(port).copied().unwrap_or(Format::DEFAULT)
+    }
+
+    fn build_compare_map(specs: &Vec<String>) -> HashMap<String, Compare> {
+        let mut compares = HashMap::new();
+        for spec in specs {
+            if let Some((port, mode)) = spec.split_once('=') {
+                match Compare::parse(mode) {
+                    Ok(compare) => {
+                        compares.insert(port.to_string(), compare);
+                    }
+                    Err(e) => eprintln!("warning: ignoring '--compare {}': {}", spec, e),
+                }
+            }
+        }
+        compares
+    }
+
+    fn compare_of(compares: &HashMap<String, Compare>, port: &str) -> Compare {
+        compares.get(port).copied().unwrap_or(Compare::DEFAULT)
+    }
+
+    fn parse_transport(s: &str) -> Transport {
+        match Transport::parse(s) {
+            Ok(transport) => transport,
+            Err(e) => {
+                eprintln!("warning: ignoring '--transport {}': {}", s, e);
+                Transport::DEFAULT
+            }
+        }
+    }
+
+    fn display(
+        emitting: bool,
+        tag: &str,
+        result: String,
+        regions: &mut Vec<(String, String)>,
+        space_next_display: &mut bool,
+    ) {
+        if emitting {
+            regions.push((tag.to_string(), result));
+            return;
+        }
+        if *space_next_display == true {
+            println!();
+        }
+        println!("{}", result);
+        *space_next_display = true;
+    }
+
+    fn region_markers(lang: &Language, tag: &str) -> (String, String) {
+        let comment = match lang {
+            Language::Vhdl => "--",
+            Language::SystemVerilog | Language::Verilog => "//",
+        };
+        (
+            format!("{0} @generated by verb (begin {1})\n", comment, tag),
+            format!("{0} @generated by verb (end {1})\n", comment, tag),
+        )
+    }
+
+    fn splice_region(contents: &str, begin: &str, end: &str, block: &str) -> String {
+        if let (Some(start), Some(stop)) = (contents.find(begin), contents.find(end)) {
+            if stop > start {
+                let mut spliced = String::new();
+                spliced.push_str(&contents[..start]);
+                spliced.push_str(begin);
+                spliced.push_str(block);
+                spliced.push('\n');
+                spliced.push_str(end);
+                spliced.push_str(&contents[stop + end.len()..]);
+                return spliced;
+            }
+        }
+        let mut appended = contents.to_string();
+        if !appended.is_empty() && !appended.ends_with('\n') {
+            appended.push('\n');
+        }
+        appended.push('\n');
+        appended.push_str(begin);
+        appended.push_str(block);
+        appended.push('\n');
+        appended.push_str(end);
+        appended
+    }
+
+    fn splice_into_file(
+        path: &str,
+        lang: &Language,
+        regions: &Vec<(String, String)>,
+        in_place: bool,
+    ) -> proc::Result {
+        let original = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("failed to read '{}': {}", path, e)),
+        };
+        let spliced = regions.iter().fold(original, |contents, (tag, block)| {
+            let (begin, end) = Self::region_markers(lang, tag);
+            Self::splice_region(&contents, &begin, &end, block)
+        });
+        if in_place == true {
+            if let Err(e) = std::fs::write(path, &spliced) {
+                return Err(format!("failed to write '{}': {}", path, e));
+            }
+        } else {
+            println!("{}", spliced);
+        }
+        Ok(())
+    }
+
+    fn run_repl(self) -> proc::Result {
+        let ports: Vec<&Net> = self.json.get_ports().iter().collect();
+        let generics = self.json.get_generics();
+        let unit = self.json.get_identifier();
+        let formats = Self::build_format_map(&self.radix);
+        let compares = Self::build_compare_map(&self.compare);
+        let mut transport = Self::parse_transport(&self.transport);
+
+        let mut exclude: Vec<String> = self.exclude;
+        let mut lang = Self::as_owned_language(self.json.get_language());
+
+        println!("{}", REPL_HELP);
+        let mut rl = match DefaultEditor::new() {
+            Ok(rl) => rl,
+            Err(e) => {
+                println!("failed to start repl: {}", e);
+                return Ok(());
+            }
+        };
+        // treat ^C/^D as "quit"
+        while let Ok(line) = rl.readline("verb> ") {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let _ = rl.add_history_entry(line);
+
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap_or("");
+            let arg = words.next();
+
+            let filtered: Vec<&Net> = ports
+                .iter()
+                .filter(|n| exclude.contains(n.get_identifier()) == false)
+                .copied()
+                .collect();
+
+            match command {
+                "if" => println!("{}", Self::bfm_snippet(&lang, &filtered, generics, &unit)),
+                "inst" => match arg {
+                    Some(name) => println!(
+                        "{}",
+                        Self::bfm_inst_snippet(&lang, &filtered, generics, &unit, name)
+                    ),
+                    None => println!("usage: inst <name>"),
+                },
+                "send" => {
+                    let inputs: Vec<&Net> = filtered
+                        .iter()
+                        .filter(|n| n.is_input())
+                        .copied()
+                        .collect();
+                    println!(
+                        "{}",
+                        Self::send_snippet(&lang, &inputs, "bfm", &formats, &transport)
+                    )
+                }
+                "recv" => {
+                    let outputs: Vec<&Net> = filtered
+                        .iter()
+                        .filter(|n| n.is_output())
+                        .copied()
+                        .collect();
+                    println!(
+                        "{}",
+                        Self::recv_snippet(
+                            &lang, &outputs, &unit, "bfm", "mdl", &formats, &compares, &transport
+                        )
+                    )
+                }
+                "list" => {
+                    print!("input vectors order:\n ");
+                    filtered
+                        .iter()
+                        .filter(|n| n.is_input())
+                        .for_each(|n| print!(" {}", n.get_identifier()));
+                    print!("\noutput vectors order:\n ");
+                    filtered
+                        .iter()
+                        .filter(|n| n.is_output())
+                        .for_each(|n| print!(" {}", n.get_identifier()));
+                    println!();
+                }
+                "exclude" => match arg {
+                    Some(port) => {
+                        if let Some(pos) = exclude.iter().position(|p| p == port) {
+                            exclude.remove(pos);
+                            println!("including '{}'", port);
+                        } else {
+                            exclude.push(port.to_string());
+                            println!("excluding '{}'", port);
+                        }
+                    }
+                    None => println!("usage: exclude <port>"),
+                },
+                "lang" => match arg {
+                    Some("vhdl") => lang = Language::Vhdl,
+                    Some("sv") => lang = Language::SystemVerilog,
+                    Some("verilog") => lang = Language::Verilog,
+                    _ => println!("usage: lang <vhdl|sv|verilog>"),
+                },
+                "transport" => match arg {
+                    Some("file") => transport = Transport::File,
+                    Some("socket") => transport = Transport::Socket,
+                    _ => println!("usage: transport <file|socket>"),
+                },
+                "help" => println!("{}", REPL_HELP),
+                "quit" | "exit" => break,
+                _ => println!("unknown command '{}' (try 'help')", command),
+            }
+        }
+        Ok(())
+    }
+
+    fn as_owned_language(lang: &Language) -> Language {
+        match lang {
+            Language::Vhdl => Language::Vhdl,
+            Language::SystemVerilog => Language::SystemVerilog,
+            Language::Verilog => Language::Verilog,
+        }
+    }
+
+    fn bfm_snippet(lang: &Language, ports: &Vec<&Net>, generics: &Vec<Net>, unit: &str) -> String {
+        match lang {
+            Language::Vhdl => Self::vhdl_to_string_bfm(ports, unit),
+            Language::SystemVerilog => Self::sv_to_string_bfm(ports, generics, unit),
+            Language::Verilog => Self::verilog_to_string_bfm(ports, unit),
+        }
+    }
+
+    fn bfm_inst_snippet(
+        lang: &Language,
+        ports: &Vec<&Net>,
+        generics: &Vec<Net>,
+        unit: &str,
+        bfm_inst: &str,
+    ) -> String {
+        match lang {
+            Language::Vhdl => Self::vhdl_to_string_bfm_inst(unit, bfm_inst),
+            Language::SystemVerilog => Self::sv_to_string_bfm_inst(unit, generics, bfm_inst),
+            Language::Verilog => Self::verilog_to_string_bfm_inst(ports, bfm_inst),
+        }
+    }
+
+    fn send_snippet(
+        lang: &Language,
+        ports: &Vec<&Net>,
+        bfm_inst: &str,
+        formats: &HashMap<String, Format>,
+        transport: &Transport,
+    ) -> String {
+        match lang {
+            Language::Vhdl => Self::vhdl_to_string_send(ports, bfm_inst, formats, transport),
+            Language::SystemVerilog => Self::sv_to_string_send(ports, bfm_inst, formats, transport),
+            Language::Verilog => Self::verilog_to_string_send(ports, bfm_inst, formats, transport),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn recv_snippet(
+        lang: &Language,
+        ports: &Vec<&Net>,
+        unit: &str,
+        bfm_inst: &str,
+        mdl_inst: &str,
+        formats: &HashMap<String, Format>,
+        compares: &HashMap<String, Compare>,
+        transport: &Transport,
+    ) -> String {
+        match lang {
+            Language::Vhdl => {
+                Self::vhdl_to_string_comp(ports, unit, bfm_inst, formats, compares, transport)
+            }
+            Language::SystemVerilog => Self::sv_to_string_comp(
+                ports, unit, bfm_inst, mdl_inst, formats, compares, transport,
+            ),
+            Language::Verilog => Self::verilog_to_string_comp(
+                ports, unit, bfm_inst, mdl_inst, formats, compares, transport,
+            ),
+        }
+    }
+
     fn vhdl_to_string_bfm(ports: &Vec<&Net>, unit: &str) -> String {
         let result = format!(
             "{0}type {1}_if is record\n",
@@ -269,17 +826,46 @@ impl Link {
         result
     }
 
-    fn vhdl_to_string_send(ports: &Vec<&Net>, bfm_inst: &str) -> String {
-        let input_fd = "i";
-        let drive_fn = "drive";
-        let result = format!("{0}procedure send(file {1}: text) is\n{2}variable row: line;\nbegin\n{2}if endfile({1}) = false then\n{3}readline({1}, row);\n", VHDL_HEAD_COMMENT, input_fd, Self::tab(1), Self::tab(2));
-        let mut result = ports.iter().fold(result, |mut acc, n| {
+    // splices `words` tokens together via the string-returning `token`
+    // overload, so a value split across columns is reconstructed in one call
+    fn vhdl_word_source(cursor: &str, words: usize) -> String {
+        if words <= 1 {
+            cursor.to_string()
+        } else {
+            (0..words)
+                .map(|_| format!("token({})", cursor))
+                .collect::<Vec<_>>()
+                .join(" & ")
+        }
+    }
+
+    fn vhdl_to_string_send(
+        ports: &Vec<&Net>,
+        bfm_inst: &str,
+        formats: &HashMap<String, Format>,
+        transport: &Transport,
+    ) -> String {
+        let header = match transport {
+            Transport::File => {
+                let input_fd = "i";
+                format!("{0}procedure send(file {1}: text) is\n{2}variable row: line;\nbegin\n{2}if endfile({1}) = false then\n{3}readline({1}, row);\n", VHDL_HEAD_COMMENT, input_fd, Self::tab(1), Self::tab(2))
+            }
+            Transport::Socket => format!(
+                "{0}procedure send is\n{1}variable row: line;\nbegin\n{1}if socket_connected({2}) = true then\n{3}socket_read_line({2}, row);\n",
+                VHDL_HEAD_COMMENT, Self::tab(1), SOCKET_ENDPOINT, Self::tab(2),
+            ),
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let drive_fn = format!("drive{}", format.radix.vhdl_suffix());
+            let source = Self::vhdl_word_source("row", format.words);
             acc.push_str(&format!(
-                "{3}{0}(row, {1}.{2});\n",
+                "{3}{0}({1}, {2}.{4});\n",
                 drive_fn,
+                source,
                 bfm_inst,
-                n.get_identifier(),
                 Self::tab(2),
+                n.get_identifier(),
             ));
             acc
         });
@@ -287,84 +873,410 @@ impl Link {
         result
     }
 
-    fn sv_to_string_send(ports: &Vec<&Net>, bfm_inst: &str) -> String {
-        let input_fd = "fd";
+    fn sv_to_string_send(
+        ports: &Vec<&Net>,
+        bfm_inst: &str,
+        formats: &HashMap<String, Format>,
+        transport: &Transport,
+    ) -> String {
         let drive_fn = "$sscanf(parse";
-        let result = format!("{0}task send(int {1});\n{2}automatic string line;\n{2}// Read next set of input values from file\n{2}if(!$feof({1})) begin\n{3}$fgets(line, {1});\n", SV_HEAD_COMMENT, input_fd, Self::tab(1), Self::tab(2));
-        let mut result = ports.iter().fold(result, |mut acc, n| {
-            acc.push_str(&format!(
-                "{3}{0}(line), \"%b\", {1}.{2});\n",
-                drive_fn,
-                bfm_inst,
-                n.get_identifier(),
-                Self::tab(2),
-            ));
+        let header = match transport {
+            Transport::File => {
+                let input_fd = "fd";
+                format!("{0}task send(int {1});\n{2}automatic string line;\n{2}// Read next set of input values from file\n{2}if(!$feof({1})) begin\n{3}$fgets(line, {1});\n", SV_HEAD_COMMENT, input_fd, Self::tab(1), Self::tab(2))
+            }
+            Transport::Socket => format!(
+                "{0}task send();\n{1}automatic string line;\n{1}// Read next set of input values from the companion model\n{1}if({2}.connected()) begin\n{3}{2}.read_line(line);\n",
+                SV_HEAD_COMMENT, Self::tab(1), SOCKET_ENDPOINT, Self::tab(2),
+            ),
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let id = n.get_identifier();
+            if format.words <= 1 {
+                acc.push_str(&format!(
+                    "{4}{0}(line), \"{3}\", {1}.{2});\n",
+                    drive_fn,
+                    bfm_inst,
+                    id,
+                    format.radix.format_spec(),
+                    Self::tab(2),
+                ));
+            } else {
+                let dest = format!("{}.{}", bfm_inst, id);
+                let spec = format.radix.format_spec().to_string();
+                acc.push_str(&Self::sv_multiword_read(
+                    &Self::tab(2),
+                    id,
+                    &dest,
+                    format.words,
+                    |w| {
+                        format!(
+                            "{2}{0}(line), \"{1}\", {3});\n",
+                            drive_fn,
+                            spec,
+                            Self::tab(2),
+                            w,
+                        )
+                    },
+                ));
+            }
             acc
         });
         result.push_str(&format!("{0}end\nendtask", Self::tab(1)));
         result
     }
 
-    fn vhdl_to_string_comp(ports: &Vec<&Net>, unit: &str, bfm_inst: &str) -> String {
-        let event_fd = "e";
-        let output_fd = "o";
-        let load_fn = "load";
-        let assert_fn = "assert_eq";
-        let result = format!("{0}procedure recv(file {1}: text; file {2}: text) is\n{4}variable row: line;\n{4}variable mdl: {3}_bfm;\nbegin\n{4}if endfile({2}) = false then\n{5}readline({2}, row);\n", VHDL_HEAD_COMMENT, event_fd, output_fd, unit, Self::tab(1), Self::tab(2));
-        let mut result = ports.iter().fold(result, |mut acc, n| {
+    // reads `words` tokens into their own locals via `read`, then
+    // concatenates them into `dest` in one assignment
+    fn sv_multiword_read(
+        tab: &str,
+        id: &str,
+        dest: &str,
+        words: usize,
+        read: impl Fn(&str) -> String,
+    ) -> String {
+        let names: Vec<String> = (0..words).map(|i| format!("{}_w{}", id, i)).collect();
+        let mut block = names.iter().fold(String::new(), |mut acc, w| {
+            acc.push_str(&format!("{0}automatic logic [31:0] {1};\n", tab, w));
+            acc
+        });
+        block = names.iter().fold(block, |mut acc, w| {
+            acc.push_str(&read(w));
+            acc
+        });
+        block.push_str(&format!("{0}{1} = {{{2}}};\n", tab, dest, names.join(", ")));
+        block
+    }
+
+    fn vhdl_to_string_comp(
+        ports: &Vec<&Net>,
+        unit: &str,
+        bfm_inst: &str,
+        formats: &HashMap<String, Format>,
+        compares: &HashMap<String, Compare>,
+        transport: &Transport,
+    ) -> String {
+        let header = match transport {
+            Transport::File => {
+                let event_fd = "e";
+                let output_fd = "o";
+                format!("{0}procedure recv(file {1}: text; file {2}: text) is\n{4}variable row: line;\n{4}variable mdl: {3}_bfm;\nbegin\n{4}if endfile({2}) = false then\n{5}readline({2}, row);\n", VHDL_HEAD_COMMENT, event_fd, output_fd, unit, Self::tab(1), Self::tab(2))
+            }
+            Transport::Socket => format!(
+                "{0}procedure recv is\n{2}variable row: line;\n{2}variable mdl: {1}_bfm;\nbegin\n{2}if socket_connected({3}) = true then\n{4}socket_read_line({3}, row);\n",
+                VHDL_HEAD_COMMENT, unit, Self::tab(1), SOCKET_ENDPOINT, Self::tab(2),
+            ),
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let load_fn = format!("load{}", format.radix.vhdl_suffix());
+            let source = Self::vhdl_word_source("row", format.words);
             acc.push_str(&format!(
-                "{2}{0}(row, mdl.{1});\n",
+                "{2}{0}({1}, mdl.{3});\n",
                 load_fn,
-                n.get_identifier(),
+                source,
                 Self::tab(2),
-            ));
-            acc.push_str(&format!(
-                "{4}{3}({0}, {1}.{2}, mdl.{2}, \"{2}\");\n",
-                event_fd,
-                bfm_inst,
                 n.get_identifier(),
-                assert_fn,
-                Self::tab(2),
             ));
+            let compare = Self::compare_of(compares, n.get_identifier());
+            if let Some(call) = compare.assert_call(
+                &format!("{}.{}", bfm_inst, n.get_identifier()),
+                &format!("mdl.{}", n.get_identifier()),
+                n.get_identifier(),
+            ) {
+                acc.push_str(&format!("{}{}", Self::tab(2), call));
+            }
             acc
         });
-        result.push_str(&format!("{0}end if;\nend procedure;", Self::tab(1)));
+        let tail = match transport {
+            Transport::File => format!("{0}end if;\nend procedure;", Self::tab(1)),
+            Transport::Socket => format!(
+                "{1}socket_confirm({2}, {3}, {4});\n{0}end if;\nend procedure;",
+                Self::tab(1),
+                Self::tab(2),
+                SOCKET_ENDPOINT,
+                bfm_inst,
+                SOCKET_CONFIRM_RETRIES,
+            ),
+        };
+        result.push_str(&tail);
         result
     }
 
-    fn sv_to_string_comp(ports: &Vec<&Net>, _unit: &str, bfm_inst: &str, mdl_inst: &str) -> String {
-        let output_fd = "fd";
+    fn sv_to_string_comp(
+        ports: &Vec<&Net>,
+        _unit: &str,
+        bfm_inst: &str,
+        mdl_inst: &str,
+        formats: &HashMap<String, Format>,
+        compares: &HashMap<String, Compare>,
+        transport: &Transport,
+    ) -> String {
         let load_fn = "$sscanf(parse";
-        let assert_fn = "assert_eq";
-        let result = format!("{0}task recv(int {3});\n{1}automatic string line;\n{1}// Read expected output values from file\n{1}if(!$feof({3})) begin\n{2}$fgets(line, {3});\n", SV_HEAD_COMMENT, Self::tab(1), Self::tab(2), output_fd);
-        let mut result = ports.iter().fold(result, |mut acc, n| {
+        let header = match transport {
+            Transport::File => {
+                let output_fd = "fd";
+                format!("{0}task recv(int {3});\n{1}automatic string line;\n{1}// Read expected output values from file\n{1}if(!$feof({3})) begin\n{2}$fgets(line, {3});\n", SV_HEAD_COMMENT, Self::tab(1), Self::tab(2), output_fd)
+            }
+            Transport::Socket => format!(
+                "{0}task recv();\n{1}automatic string line;\n{1}// Read expected output values from the companion model\n{1}if({2}.connected()) begin\n{3}{2}.read_line(line);\n",
+                SV_HEAD_COMMENT, Self::tab(1), SOCKET_ENDPOINT, Self::tab(2),
+            ),
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let id = n.get_identifier();
+            if format.words <= 1 {
+                acc.push_str(&format!(
+                    "{4}{0}(line), \"{3}\", {1}.{2});\n",
+                    load_fn,
+                    mdl_inst,
+                    id,
+                    format.radix.format_spec(),
+                    Self::tab(2)
+                ));
+            } else {
+                let dest = format!("{}.{}", mdl_inst, id);
+                let spec = format.radix.format_spec().to_string();
+                acc.push_str(&Self::sv_multiword_read(
+                    &Self::tab(2),
+                    id,
+                    &dest,
+                    format.words,
+                    |w| {
+                        format!(
+                            "{2}{0}(line), \"{1}\", {3});\n",
+                            load_fn,
+                            spec,
+                            Self::tab(2),
+                            w,
+                        )
+                    },
+                ));
+            }
+            acc
+        });
+        result.push_str(&format!("{0}end\n", Self::tab(1)));
+        let checks = format!("{0}// Compare received ouputs with expected outputs\n", Self::tab(1));
+        let mut checks = ports.iter().fold(checks, |mut acc, n| {
+            let compare = Self::compare_of(compares, n.get_identifier());
+            if let Some(call) = compare.assert_call(
+                &format!("{}.{}", bfm_inst, n.get_identifier()),
+                &format!("{}.{}", mdl_inst, n.get_identifier()),
+                n.get_identifier(),
+            ) {
+                acc.push_str(&format!("{}{}", Self::tab(1), call));
+            }
+            acc
+        });
+        if let Transport::Socket = transport {
+            checks.push_str(&format!(
+                "{0}{1}.confirm({2}, {3});\n",
+                Self::tab(1),
+                SOCKET_ENDPOINT,
+                bfm_inst,
+                SOCKET_CONFIRM_RETRIES,
+            ));
+        }
+        result.push_str(&checks);
+        result.push_str(&format!("{0}endtask", Self::tab(0)));
+        result
+    }
+
+    // classic Verilog has neither interfaces nor records, and --if-inst
+    // already declares the wire/reg bundle under its own instance name, so
+    // there is nothing separate for --if to declare
+    fn verilog_to_string_bfm(_ports: &Vec<&Net>, _unit: &str) -> String {
+        String::from(
+            "// Verilog has no interface/record construct, so there is no\n// separate bfm to declare here; --if-inst <name> emits the wire\n// bundle directly under <name>.",
+        )
+    }
+
+    fn verilog_to_string_bfm_inst(ports: &Vec<&Net>, bfm_inst: &str) -> String {
+        let result = ports.iter().fold(String::new(), |mut acc, n| {
             acc.push_str(&format!(
-                "{3}{0}(line), \"%b\", {1}.{2});\n",
-                load_fn,
-                mdl_inst,
+                "{0} {1} {2}_{3};\n",
+                Self::v_net_kind(n),
+                n.get_type(),
+                bfm_inst,
                 n.get_identifier(),
-                Self::tab(2)
             ));
             acc
         });
+        result.trim_end().to_string()
+    }
+
+    // like sv_multiword_read, but the temporaries are declared in a named
+    // block since classic Verilog has no `automatic` locals
+    fn v_multiword_read(
+        id: &str,
+        dest: &str,
+        words: usize,
+        read: impl Fn(&str) -> String,
+    ) -> String {
+        let names: Vec<String> = (0..words).map(|i| format!("w{}", i)).collect();
+        let mut block = format!("{0}begin: mw_{1}\n", Self::tab(2), id);
+        block = names.iter().fold(block, |mut acc, w| {
+            acc.push_str(&format!("{0}integer {1};\n", Self::tab(3), w));
+            acc
+        });
+        block = names.iter().fold(block, |mut acc, w| {
+            acc.push_str(&read(w));
+            acc
+        });
+        block.push_str(&format!(
+            "{0}{1} = {{{2}}};\n",
+            Self::tab(3),
+            dest,
+            names.join(", ")
+        ));
+        block.push_str(&format!("{0}end\n", Self::tab(2)));
+        block
+    }
+
+    fn verilog_to_string_send(
+        ports: &Vec<&Net>,
+        bfm_inst: &str,
+        formats: &HashMap<String, Format>,
+        transport: &Transport,
+    ) -> String {
+        let read_fn = "$fscanf";
+        let header = match transport {
+            Transport::File => {
+                let input_fd = "fd";
+                format!("{0}task send(input integer {1});\n{2}integer code;\n{2}// Read next set of input values from file\n{2}if (!$feof({1})) begin\n", V_HEAD_COMMENT, input_fd, Self::tab(1))
+            }
+            Transport::Socket => format!(
+                "{0}task send;\n{1}integer code;\n{1}// Read next set of input values from the companion model\n{1}if ($socket_connected({2})) begin\n",
+                V_HEAD_COMMENT, Self::tab(1), SOCKET_ENDPOINT,
+            ),
+        };
+        let input_fd = match transport {
+            Transport::File => "fd",
+            Transport::Socket => SOCKET_ENDPOINT,
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let id = n.get_identifier();
+            if format.words <= 1 {
+                acc.push_str(&format!(
+                    "{4}code = {0}({1}, \"{3}\", {2}_{5});\n",
+                    read_fn,
+                    input_fd,
+                    bfm_inst,
+                    format.radix.format_spec(),
+                    Self::tab(2),
+                    id,
+                ));
+            } else {
+                let dest = format!("{}_{}", bfm_inst, id);
+                let spec = format.radix.format_spec().to_string();
+                acc.push_str(&Self::v_multiword_read(id, &dest, format.words, |w| {
+                    format!(
+                        "{3}code = {0}({1}, \"{2}\", {4});\n",
+                        read_fn,
+                        input_fd,
+                        spec,
+                        Self::tab(3),
+                        w,
+                    )
+                }));
+            }
+            acc
+        });
+        result.push_str(&format!("{0}end\nendtask", Self::tab(1)));
+        result
+    }
+
+    fn verilog_to_string_comp(
+        ports: &Vec<&Net>,
+        _unit: &str,
+        bfm_inst: &str,
+        mdl_inst: &str,
+        formats: &HashMap<String, Format>,
+        compares: &HashMap<String, Compare>,
+        transport: &Transport,
+    ) -> String {
+        let read_fn = "$fscanf";
+        let header = match transport {
+            Transport::File => {
+                let output_fd = "fd";
+                format!("{0}task recv(input integer {2});\n{1}integer code;\n{1}// Read expected output values from file\n{1}if (!$feof({2})) begin\n", V_HEAD_COMMENT, Self::tab(1), output_fd)
+            }
+            Transport::Socket => format!(
+                "{0}task recv;\n{1}integer code;\n{1}// Read expected output values from the companion model\n{1}if ($socket_connected({2})) begin\n",
+                V_HEAD_COMMENT, Self::tab(1), SOCKET_ENDPOINT,
+            ),
+        };
+        let output_fd = match transport {
+            Transport::File => "fd",
+            Transport::Socket => SOCKET_ENDPOINT,
+        };
+        let mut result = ports.iter().fold(header, |mut acc, n| {
+            let format = Self::format_of(formats, n.get_identifier());
+            let id = n.get_identifier();
+            if format.words <= 1 {
+                acc.push_str(&format!(
+                    "{4}code = {0}({1}, \"{3}\", {2}_{5});\n",
+                    read_fn,
+                    output_fd,
+                    mdl_inst,
+                    format.radix.format_spec(),
+                    Self::tab(2),
+                    id,
+                ));
+            } else {
+                let dest = format!("{}_{}", mdl_inst, id);
+                let spec = format.radix.format_spec().to_string();
+                acc.push_str(&Self::v_multiword_read(id, &dest, format.words, |w| {
+                    format!(
+                        "{3}code = {0}({1}, \"{2}\", {4});\n",
+                        read_fn,
+                        output_fd,
+                        spec,
+                        Self::tab(3),
+                        w,
+                    )
+                }));
+            }
+            acc
+        });
         result.push_str(&format!("{0}end\n", Self::tab(1)));
         let checks = format!("{0}// Compare received ouputs with expected outputs\n", Self::tab(1));
-        let checks = ports.iter().fold(checks, |mut acc, n| {
-            acc.push_str(&format!(
-                "{1}{2}({3}.{0}, {4}.{0}, \"{0}\");\n",
+        let mut checks = ports.iter().fold(checks, |mut acc, n| {
+            let compare = Self::compare_of(compares, n.get_identifier());
+            if let Some(call) = compare.assert_call(
+                &format!("{}_{}", bfm_inst, n.get_identifier()),
+                &format!("{}_{}", mdl_inst, n.get_identifier()),
                 n.get_identifier(),
+            ) {
+                acc.push_str(&format!("{}{}", Self::tab(1), call));
+            }
+            acc
+        });
+        if let Transport::Socket = transport {
+            checks.push_str(&format!(
+                "{0}code = $socket_confirm({1}, {2}, {3});\n",
                 Self::tab(1),
-                assert_fn,
+                SOCKET_ENDPOINT,
                 bfm_inst,
-                mdl_inst,
+                SOCKET_CONFIRM_RETRIES,
             ));
-            acc
-        });
+        }
         result.push_str(&checks);
         result.push_str(&format!("{0}endtask", Self::tab(0)));
         result
     }
 
+    fn v_net_kind(n: &Net) -> &'static str {
+        if n.is_input() {
+            "reg"
+        } else {
+            "wire"
+        }
+    }
+
     /// Computes the number of characters required for the longest known
     /// identifier.
     fn _longest_id_len(ids: Vec<&String>) -> usize {